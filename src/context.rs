@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use ErrorMode;
+use Filter;
+use error::Error;
+use value::Value;
+use filters::standard_filters;
+
+/// The state threaded through a render: the variable bindings, the registered
+/// filters, the active [ErrorMode](../enum.ErrorMode.html), and — under `Warn`
+/// mode — the warnings accumulated along the way.
+pub struct Context {
+    values: HashMap<String, Value>,
+    filters: HashMap<String, Box<Filter>>,
+    error_mode: ErrorMode,
+    warnings: Vec<Error>,
+}
+
+impl Default for Context {
+    fn default() -> Context {
+        Context::new()
+    }
+}
+
+impl Context {
+    /// A fresh context with the standard filters registered and the default
+    /// [ErrorMode](../enum.ErrorMode.html).
+    pub fn new() -> Context {
+        Context::with_error_mode(ErrorMode::default())
+    }
+
+    /// A fresh context seeded with a specific [ErrorMode](../enum.ErrorMode.html).
+    /// A template returned by `liquid::parse` replaces this mode with the one
+    /// from its `LiquidOptions` when it is rendered.
+    pub fn with_error_mode(error_mode: ErrorMode) -> Context {
+        Context {
+            values: HashMap::new(),
+            filters: standard_filters(),
+            error_mode: error_mode,
+            warnings: vec![],
+        }
+    }
+
+    pub fn get_val(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+
+    /// Resolves a dotted/bracketed path such as `order.items[0].title`, walking
+    /// `Value::Object` maps by key and `Value::Array`s by integer index.
+    /// Returns `None` if a segment is missing, an index is out of range, or the
+    /// path steps into a value of the wrong shape.
+    pub fn get_path(&self, path: &str) -> Option<&Value> {
+        let segments = match parse_path(path) {
+            Some(segments) => segments,
+            None => return None,
+        };
+        let mut iter = segments.iter();
+        let first = match iter.next() {
+            Some(&PathSegment::Key(ref key)) => key,
+            _ => return None,
+        };
+        let mut current = match self.values.get(first) {
+            Some(value) => value,
+            None => return None,
+        };
+        for segment in iter {
+            current = match (current, segment) {
+                (&Value::Object(ref map), &PathSegment::Key(ref key)) => {
+                    match map.get(key) {
+                        Some(value) => value,
+                        None => return None,
+                    }
+                }
+                (&Value::Array(ref array), &PathSegment::Index(index)) => {
+                    match array.get(index) {
+                        Some(value) => value,
+                        None => return None,
+                    }
+                }
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    pub fn set_val(&mut self, name: &str, value: Value) {
+        self.values.insert(name.to_owned(), value);
+    }
+
+    pub fn error_mode(&self) -> ErrorMode {
+        self.error_mode
+    }
+
+    pub fn set_error_mode(&mut self, error_mode: ErrorMode) {
+        self.error_mode = error_mode;
+    }
+
+    pub fn add_filter(&mut self, name: &str, filter: Box<Filter>) {
+        self.filters.insert(name.to_owned(), filter);
+    }
+
+    pub fn get_filter(&self, name: &str) -> Option<&Box<Filter>> {
+        self.filters.get(name)
+    }
+
+    /// Records a non-fatal condition encountered under `Warn` mode.
+    pub fn warn(&mut self, error: Error) {
+        self.warnings.push(error);
+    }
+
+    /// The warnings accumulated under `Warn` mode. They are not cleared between
+    /// renders, so a reused context keeps those of earlier renders too.
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+}
+
+/// A single step of a variable path. `order.items[0].title` decomposes into
+/// `Key("order")`, `Key("items")`, `Index(0)`, `Key("title")`.
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Breaks a dotted/bracketed path into its segments. Bracketed indices become
+/// their own segment; a non-integer index (e.g. `items[x]`) makes the whole
+/// path unresolvable rather than being silently dropped, so `None` is returned.
+fn parse_path(path: &str) -> Option<Vec<PathSegment>> {
+    let mut segments = vec![];
+    let mut current = String::new();
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !current.is_empty() {
+                    segments.push(PathSegment::Key(current.clone()));
+                    current.clear();
+                }
+            }
+            '[' => {
+                if !current.is_empty() {
+                    segments.push(PathSegment::Key(current.clone()));
+                    current.clear();
+                }
+                let mut index = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == ']' {
+                        chars.next();
+                        break;
+                    }
+                    index.push(c);
+                    chars.next();
+                }
+                match index.parse::<usize>() {
+                    Ok(i) => segments.push(PathSegment::Index(i)),
+                    Err(_) => return None,
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        segments.push(PathSegment::Key(current));
+    }
+    Some(segments)
+}
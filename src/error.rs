@@ -0,0 +1,120 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::result;
+
+/// A byte span `(start, end)` into the original template source. The range is
+/// half-open: `start` is inclusive, `end` exclusive, matching `str` slicing.
+///
+/// Spans are tracked at [Element](../lexer/enum.Element.html) granularity — the
+/// whole `{{ ... }}` / `{% ... %}` marker or run of raw text. Individual
+/// [Tokens](../lexer/enum.Token.html) inside a marker are not separately
+/// spanned, so a diagnostic underlines the offending marker rather than the
+/// single token within it. Per-token spans would require wrapping every `Token`
+/// and reworking the tag builders that pattern-match on bare `Token` values.
+pub type Span = (usize, usize);
+
+/// The errors that can arise while parsing or rendering a template.
+///
+/// `Parser` and `Render` optionally carry the [Span](type.Span.html) of the
+/// offending element so that [report](enum.Error.html#method.report) can point
+/// at its location in the source.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    Parser(String, Option<Span>),
+    Render(String, Option<Span>),
+    Filter(String),
+    Other(String),
+}
+
+impl Error {
+    /// Builds a parser error without a known location.
+    pub fn parser<T: Into<String>>(msg: T) -> Error {
+        Error::Parser(msg.into(), None)
+    }
+
+    /// Builds a render error without a known location.
+    pub fn render<T: Into<String>>(msg: T) -> Error {
+        Error::Render(msg.into(), None)
+    }
+
+    /// Attaches `span` to a spanless `Parser`/`Render` error. Errors that
+    /// already carry a span (a more specific inner location) are left untouched,
+    /// so wrapping an error with an enclosing element's span never clobbers a
+    /// narrower one.
+    pub fn span(self, span: Span) -> Error {
+        match self {
+            Error::Parser(msg, None) => Error::Parser(msg, Some(span)),
+            Error::Render(msg, None) => Error::Render(msg, Some(span)),
+            other => other,
+        }
+    }
+
+    /// Produces a multi-line diagnostic against the original template `source`:
+    /// the 1-based line/column, the offending line, and a caret underline under
+    /// the span. Errors without a span (or variants that carry none) fall back
+    /// to the terse [Display](enum.Error.html) form.
+    pub fn report(&self, source: &str) -> String {
+        let (msg, span) = match *self {
+            Error::Parser(ref msg, span) => (msg, span),
+            Error::Render(ref msg, span) => (msg, span),
+            _ => return format!("{}", self),
+        };
+        let (start, end) = match span {
+            Some(span) => span,
+            None => return format!("{}", self),
+        };
+
+        let mut line_start = 0;
+        let mut line = 1;
+        for (i, c) in source.char_indices() {
+            if i >= start {
+                break;
+            }
+            if c == '\n' {
+                line_start = i + 1;
+                line += 1;
+            }
+        }
+        let line_end = source[start..]
+            .find('\n')
+            .map(|offset| start + offset)
+            .unwrap_or_else(|| source.len());
+        let column = start - line_start;
+        let width = if end > start { end - start } else { 1 };
+
+        let padding: String = ::std::iter::repeat(' ').take(column).collect();
+        let underline: String = ::std::iter::repeat('^').take(width).collect();
+        format!("{}:{}: {}\n{}\n{}{}",
+                line,
+                column + 1,
+                msg,
+                &source[line_start..line_end],
+                padding,
+                underline)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Parser(ref msg, _) => write!(f, "Parsing error: {}", msg),
+            Error::Render(ref msg, _) => write!(f, "Rendering error: {}", msg),
+            Error::Filter(ref msg) => write!(f, "Filter error: {}", msg),
+            Error::Other(ref msg) => write!(f, "Error: {}", msg),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::Parser(..) => "parsing error",
+            Error::Render(..) => "rendering error",
+            Error::Filter(..) => "filter error",
+            Error::Other(..) => "error",
+        }
+    }
+}
+
+/// A convenient `Result` alias for the liquid [Error](enum.Error.html).
+pub type Result<T> = result::Result<T, Error>;
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::fmt;
+use Filter;
+use value::Value;
+
+/// The reason a filter refused to produce a value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterError {
+    InvalidType(String),
+    InvalidArgumentCount(String),
+    InvalidArgument(u16, String),
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FilterError::InvalidType(ref e) => write!(f, "Invalid type : {}", e),
+            FilterError::InvalidArgumentCount(ref e) => {
+                write!(f, "Invalid number of arguments : {}", e)
+            }
+            FilterError::InvalidArgument(pos, ref e) => {
+                write!(f, "Invalid argument given at position {} : {}", pos, e)
+            }
+        }
+    }
+}
+
+/// The result of applying a [Filter](../type.Filter.html): the filtered value or
+/// a [FilterError](enum.FilterError.html).
+pub type FilterResult = Result<Value, FilterError>;
+
+/// The filters registered on every fresh [Context](../struct.Context.html).
+pub fn standard_filters() -> HashMap<String, Box<Filter>> {
+    let mut filters: HashMap<String, Box<Filter>> = HashMap::new();
+    filters.insert("upcase".to_owned(), Box::new(upcase as fn(&Value, &[Value]) -> FilterResult));
+    filters.insert("downcase".to_owned(),
+                   Box::new(downcase as fn(&Value, &[Value]) -> FilterResult));
+    filters.insert("capitalize".to_owned(),
+                   Box::new(capitalize as fn(&Value, &[Value]) -> FilterResult));
+    filters.insert("size".to_owned(), Box::new(size as fn(&Value, &[Value]) -> FilterResult));
+    filters.insert("replace".to_owned(),
+                   Box::new(replace as fn(&Value, &[Value]) -> FilterResult));
+    filters
+}
+
+fn no_args(name: &str, args: &[Value]) -> Result<(), FilterError> {
+    if args.is_empty() {
+        Ok(())
+    } else {
+        Err(FilterError::InvalidArgumentCount(format!("{} expects no arguments", name)))
+    }
+}
+
+fn upcase(input: &Value, args: &[Value]) -> FilterResult {
+    try!(no_args("upcase", args));
+    match *input {
+        Value::Str(ref s) => Ok(Value::Str(s.to_uppercase())),
+        _ => Err(FilterError::InvalidType("String expected".to_owned())),
+    }
+}
+
+fn downcase(input: &Value, args: &[Value]) -> FilterResult {
+    try!(no_args("downcase", args));
+    match *input {
+        Value::Str(ref s) => Ok(Value::Str(s.to_lowercase())),
+        _ => Err(FilterError::InvalidType("String expected".to_owned())),
+    }
+}
+
+fn capitalize(input: &Value, args: &[Value]) -> FilterResult {
+    try!(no_args("capitalize", args));
+    match *input {
+        Value::Str(ref s) => {
+            let mut chars = s.chars();
+            let capitalized = match chars.next() {
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            };
+            Ok(Value::Str(capitalized))
+        }
+        _ => Err(FilterError::InvalidType("String expected".to_owned())),
+    }
+}
+
+fn size(input: &Value, args: &[Value]) -> FilterResult {
+    try!(no_args("size", args));
+    match *input {
+        Value::Str(ref s) => Ok(Value::Num(s.len() as f32)),
+        Value::Array(ref a) => Ok(Value::Num(a.len() as f32)),
+        Value::Object(ref o) => Ok(Value::Num(o.len() as f32)),
+        _ => Err(FilterError::InvalidType("String, Array or Object expected".to_owned())),
+    }
+}
+
+fn replace(input: &Value, args: &[Value]) -> FilterResult {
+    let search = match args.get(0) {
+        Some(&Value::Str(ref s)) => s,
+        _ => return Err(FilterError::InvalidArgument(0, "String expected".to_owned())),
+    };
+    let with = match args.get(1) {
+        Some(&Value::Str(ref s)) => s,
+        _ => return Err(FilterError::InvalidArgument(1, "String expected".to_owned())),
+    };
+    match *input {
+        Value::Str(ref s) => Ok(Value::Str(s.replace(search.as_str(), with))),
+        _ => Err(FilterError::InvalidType("String expected".to_owned())),
+    }
+}
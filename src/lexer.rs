@@ -0,0 +1,137 @@
+use regex::Regex;
+use error::{Result, Span};
+use self::Token::*;
+use self::Element::*;
+
+/// A comparison operator recognised inside tag arguments (`{% if a == b %}`).
+#[derive(Clone, Debug, PartialEq)]
+pub enum ComparisonOperator {
+    Equals,
+    NotEquals,
+    LessThan,
+    GreaterThan,
+    LessThanEquals,
+    GreaterThanEquals,
+    Contains,
+}
+
+/// A single lexical token inside an expression or tag.
+///
+/// Tokens do not carry their own [Span](../error/type.Span.html): location
+/// tracking is done at [Element](enum.Element.html) granularity (see the `Span`
+/// docs), which the tag builders — matching on bare `Token` values — rely on.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Pipe,
+    Dot,
+    Colon,
+    Comma,
+    OpenSquare,
+    CloseSquare,
+    OpenRound,
+    CloseRound,
+    Question,
+    Dash,
+    DotDot,
+    Assignment,
+    Identifier(String),
+    StringLiteral(String),
+    NumberLiteral(f32),
+    BooleanLiteral(bool),
+    Comparison(ComparisonOperator),
+}
+
+/// A top-level piece of a template, carrying the byte [Span](../error/type.Span.html)
+/// it occupies in the original source so errors can be located precisely.
+///
+/// * `Expression` is a `{{ ... }}` output.
+/// * `Tag` is a `{% ... %}` tag or block marker.
+/// * `Raw` is the literal text between markers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Element {
+    Expression(Vec<Token>, String, Span),
+    Tag(Vec<Token>, String, Span),
+    Raw(String, Span),
+}
+
+lazy_static! {
+    static ref MARKUP: Regex = Regex::new(r"\{\{.*?\}\}|\{%.*?%\}").unwrap();
+    static ref SPLIT: Regex = Regex::new(concat!(
+        r"'[^']*'|",
+        "\"[^\"]*\"|",
+        r"==|!=|<=|>=|\.\.|",
+        r"[a-zA-Z_][a-zA-Z0-9_-]*(?:\.[a-zA-Z_][a-zA-Z0-9_-]*|\[[0-9]+\])*|",
+        r"-?[0-9]+(?:\.[0-9]+)?|",
+        r"[|:,\[\]\(\)\?<>=\.-]"
+    )).unwrap();
+}
+
+/// Splits a markup block's inner text into its constituent tokens.
+fn granularize(block: &str) -> Result<Vec<Token>> {
+    let mut result = vec![];
+    for mat in SPLIT.find_iter(block) {
+        let token = mat.as_str();
+        result.push(match token {
+            "|" => Pipe,
+            "." => Dot,
+            ":" => Colon,
+            "," => Comma,
+            "[" => OpenSquare,
+            "]" => CloseSquare,
+            "(" => OpenRound,
+            ")" => CloseRound,
+            "?" => Question,
+            "-" => Dash,
+            ".." => DotDot,
+            "=" => Assignment,
+            "==" => Comparison(ComparisonOperator::Equals),
+            "!=" => Comparison(ComparisonOperator::NotEquals),
+            "<=" => Comparison(ComparisonOperator::LessThanEquals),
+            ">=" => Comparison(ComparisonOperator::GreaterThanEquals),
+            "<" => Comparison(ComparisonOperator::LessThan),
+            ">" => Comparison(ComparisonOperator::GreaterThan),
+            "contains" => Comparison(ComparisonOperator::Contains),
+            "true" => BooleanLiteral(true),
+            "false" => BooleanLiteral(false),
+            x if x.starts_with('"') || x.starts_with('\'') => {
+                StringLiteral(x[1..x.len() - 1].to_owned())
+            }
+            x => {
+                match x.parse::<f32>() {
+                    Ok(n) => NumberLiteral(n),
+                    Err(_) => Identifier(x.to_owned()),
+                }
+            }
+        });
+    }
+    Ok(result)
+}
+
+/// Tokenizes a template into a flat list of [Elements](enum.Element.html),
+/// each tagged with its byte span into `text`.
+pub fn tokenize(text: &str) -> Result<Vec<Element>> {
+    let mut elements = vec![];
+    let mut cursor = 0;
+
+    for mat in MARKUP.find_iter(text) {
+        let (start, end) = (mat.start(), mat.end());
+        if start > cursor {
+            elements.push(Raw(text[cursor..start].to_owned(), (cursor, start)));
+        }
+
+        let block = mat.as_str();
+        let inner = &block[2..block.len() - 2];
+        if block.starts_with("{{") {
+            elements.push(Expression(try!(granularize(inner)), block.to_owned(), (start, end)));
+        } else {
+            elements.push(Tag(try!(granularize(inner)), block.to_owned(), (start, end)));
+        }
+        cursor = end;
+    }
+
+    if cursor < text.len() {
+        elements.push(Raw(text[cursor..].to_owned(), (cursor, text.len())));
+    }
+
+    Ok(elements)
+}
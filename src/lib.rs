@@ -56,8 +56,18 @@ mod variable;
 mod context;
 
 /// The ErrorMode to use.
-/// This currently does not have an effect, until
-/// ErrorModes are properly implemented.
+///
+/// Governs how `Renderable::render` reacts to undefined variables, unknown
+/// filters and iterating over a value that is not an array:
+///
+/// * `Strict` returns an `Err` as soon as any such condition is hit.
+/// * `Lax` swallows them: an undefined variable or unknown filter renders as an
+///   empty string and iterating a non-array yields nothing.
+/// * `Warn` renders like `Lax` but records each condition as a warning on the
+///   [Context](struct.Context.html), retrievable once rendering finishes.
+///
+/// Parse errors (including unknown tags), errors returned by a filter and
+/// non-numeric range bounds are errors in every mode.
 #[derive(Clone, Copy)]
 pub enum ErrorMode {
     Strict,
@@ -110,6 +120,15 @@ pub type Tag = Fn(&str, &[Token], &LiquidOptions) -> Box<Renderable>;
 /// the block, a Vec of all [Elements](lexer/enum.Element.html) inside the block and the global [LiquidOptions](struct.LiquidOptions.html).
 pub type Block = Fn(&str, &[Token], Vec<Element>, &LiquidOptions) -> Result<Box<Renderable>>;
 
+/// A trait for creating custom filters. This is a simple type alias for a function.
+///
+/// This function will be called whenever the filter is invoked in an output
+/// expression (`{{ value | filter: arg1, arg2 }}`). It receives the already
+/// evaluated input [Value](enum.Value.html) and the slice of evaluated
+/// positional arguments, and returns the filtered [Value](enum.Value.html) or a
+/// [FilterError](enum.FilterError.html).
+pub type Filter = Fn(&Value, &[Value]) -> FilterResult;
+
 /// Any object (tag/block) that can be rendered by liquid must implement this trait.
 pub trait Renderable{
     fn render(&self, context: &mut Context) -> Result<Option<String>>;
@@ -119,6 +138,7 @@ pub trait Renderable{
 pub struct LiquidOptions {
     pub blocks: HashMap<String, Box<Block>>,
     pub tags: HashMap<String, Box<Tag>>,
+    pub filters: HashMap<String, Box<Filter>>,
     pub error_mode: ErrorMode,
 }
 
@@ -144,5 +164,6 @@ pub fn parse(text: &str, options: LiquidOptions) -> Result<Template> {
     options.blocks.insert("for".to_owned(), Box::new(for_block));
     options.blocks.insert("comment".to_owned(), Box::new(comment_block));
 
-    parser::parse(&tokens, &options).map(Template::new)
+    let error_mode = options.error_mode;
+    parser::parse(&tokens, &options).map(|elements| Template::new(elements).with_error_mode(error_mode))
 }
@@ -0,0 +1,161 @@
+use Renderable;
+use ErrorMode;
+use context::Context;
+use value::Value;
+use variable::Variable;
+use lexer::Token;
+use lexer::Token::*;
+use error::{Error, Result};
+
+/// A single component of an output or filter argument: either a literal value
+/// baked in at parse time or a variable path resolved against the context.
+pub enum Argument {
+    Var(Variable),
+    Val(Value),
+}
+
+impl Argument {
+    /// Evaluates the argument to a concrete [Value](../value/enum.Value.html).
+    /// An unknown variable is an error under `Strict`, an empty string under
+    /// `Lax`, and an empty string plus a recorded warning under `Warn`.
+    pub fn evaluate(&self, context: &mut Context) -> Result<Value> {
+        match *self {
+            Argument::Val(ref value) => Ok(value.clone()),
+            Argument::Var(ref variable) => {
+                match variable.resolve(context) {
+                    Some(value) => Ok(value),
+                    None => {
+                        let error = Error::render(format!("Unknown variable '{}'",
+                                                          variable.name()));
+                        match context.error_mode() {
+                            ErrorMode::Strict => Err(error),
+                            ErrorMode::Warn => {
+                                context.warn(error);
+                                Ok(Value::Str("".to_owned()))
+                            }
+                            ErrorMode::Lax => Ok(Value::Str("".to_owned())),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A single filter application in a pipeline: a name and its (unevaluated)
+/// positional arguments.
+pub struct FilterPrototype {
+    name: String,
+    arguments: Vec<Argument>,
+}
+
+impl FilterPrototype {
+    pub fn new(name: &str, arguments: Vec<Argument>) -> FilterPrototype {
+        FilterPrototype {
+            name: name.to_owned(),
+            arguments: arguments,
+        }
+    }
+}
+
+/// A `{{ ... }}` output: a base [Argument](enum.Argument.html) folded through a
+/// chain of filters.
+pub struct Output {
+    entry: Argument,
+    filters: Vec<FilterPrototype>,
+}
+
+impl Output {
+    pub fn new(entry: Argument, filters: Vec<FilterPrototype>) -> Output {
+        Output {
+            entry: entry,
+            filters: filters,
+        }
+    }
+
+    fn apply_filters(&self, context: &mut Context) -> Result<Value> {
+        let mut entry = try!(self.entry.evaluate(context));
+        for filter in &self.filters {
+            let mut arguments = vec![];
+            for argument in &filter.arguments {
+                arguments.push(try!(argument.evaluate(context)));
+            }
+            let outcome = match context.get_filter(&filter.name) {
+                Some(f) => Some(f(&entry, &arguments)),
+                None => None,
+            };
+            match outcome {
+                Some(Ok(value)) => entry = value,
+                Some(Err(error)) => return Err(Error::Filter(format!("{}", error))),
+                None => {
+                    let error = Error::render(format!("Filter '{}' not implemented",
+                                                      filter.name));
+                    match context.error_mode() {
+                        ErrorMode::Strict => return Err(error),
+                        ErrorMode::Warn => {
+                            context.warn(error);
+                            entry = Value::Str("".to_owned());
+                        }
+                        ErrorMode::Lax => entry = Value::Str("".to_owned()),
+                    }
+                }
+            }
+        }
+        Ok(entry)
+    }
+}
+
+impl Renderable for Output {
+    fn render(&self, context: &mut Context) -> Result<Option<String>> {
+        let value = try!(self.apply_filters(context));
+        Ok(Some(value.to_string()))
+    }
+}
+
+/// Parses the argument at `token`: a literal becomes `Val`, an identifier
+/// becomes a variable-path `Var`.
+pub fn parse_argument(token: Option<&Token>) -> Result<Argument> {
+    match token {
+        Some(&StringLiteral(ref s)) => Ok(Argument::Val(Value::Str(s.clone()))),
+        Some(&NumberLiteral(n)) => Ok(Argument::Val(Value::Num(n))),
+        Some(&BooleanLiteral(b)) => Ok(Argument::Val(Value::Bool(b))),
+        Some(&Identifier(ref id)) => Ok(Argument::Var(Variable::new(id))),
+        x => Err(Error::parser(format!("Expected an expression, found {:?}", x))),
+    }
+}
+
+/// Parses the token stream of a `{{ ... }}` output into a base expression
+/// followed by zero or more `| filter: a, b` applications.
+pub fn parse_output(tokens: &[Token]) -> Result<Output> {
+    let mut iter = tokens.iter().peekable();
+    let entry = try!(parse_argument(iter.next()));
+
+    let mut filters = vec![];
+    while let Some(&&Pipe) = iter.peek() {
+        iter.next();
+        let name = match iter.next() {
+            Some(&Identifier(ref name)) => name.clone(),
+            x => return Err(Error::parser(format!("Expected a filter name, found {:?}", x))),
+        };
+
+        let mut arguments = vec![];
+        if let Some(&&Colon) = iter.peek() {
+            iter.next();
+            loop {
+                arguments.push(try!(parse_argument(iter.next())));
+                match iter.peek() {
+                    Some(&&Comma) => {
+                        iter.next();
+                    }
+                    _ => break,
+                }
+            }
+        }
+        filters.push(FilterPrototype::new(&name, arguments));
+    }
+
+    match iter.next() {
+        None => Ok(Output::new(entry, filters)),
+        x => Err(Error::parser(format!("Unexpected token {:?} in output", x))),
+    }
+}
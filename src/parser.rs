@@ -0,0 +1,95 @@
+use std::slice::Iter;
+use Renderable;
+use LiquidOptions;
+use lexer::Element;
+use lexer::Element::*;
+use lexer::Token;
+use lexer::Token::Identifier;
+use error::{Error, Result};
+use text::Text;
+use output;
+
+/// Turns a flat list of [Elements](../lexer/enum.Element.html) into a list of
+/// [Renderables](../trait.Renderable.html). Parser errors are tagged with the
+/// span of the element that produced them so callers can render a source
+/// snippet via [Error::report](../error/enum.Error.html#method.report).
+pub fn parse(elements: &[Element], options: &LiquidOptions) -> Result<Vec<Box<Renderable>>> {
+    let mut ret = vec![];
+    let mut iter = elements.iter();
+
+    while let Some(element) = iter.next() {
+        match *element {
+            Expression(ref tokens, _, span) => {
+                // A `{{ name }}` whose first token names a registered tag renders
+                // that tag; anything else is an output expression.
+                match tokens.first() {
+                    Some(&Identifier(ref name)) if options.tags.contains_key(name) => {
+                        let tag = options.tags.get(name).unwrap();
+                        ret.push(tag(name, &tokens[1..], options));
+                    }
+                    _ => {
+                        let output = try!(output::parse_output(tokens).map_err(|e| e.span(span)));
+                        ret.push(Box::new(output) as Box<Renderable>);
+                    }
+                }
+            }
+            Tag(ref tokens, _, span) => {
+                ret.push(try!(parse_tag(&mut iter, tokens, options).map_err(|e| e.span(span))));
+            }
+            Raw(ref text, _) => ret.push(Box::new(Text::new(text)) as Box<Renderable>),
+        }
+    }
+
+    Ok(ret)
+}
+
+/// Dispatches a `{% ... %}` marker to a registered tag or block. Blocks consume
+/// elements up to their matching `end<name>` tag, stepping over any nested
+/// blocks of the same or a different kind.
+fn parse_tag(iter: &mut Iter<Element>,
+             tokens: &[Token],
+             options: &LiquidOptions)
+             -> Result<Box<Renderable>> {
+    match tokens.first() {
+        Some(&Identifier(ref name)) if options.tags.contains_key(name) => {
+            let tag = options.tags.get(name).unwrap();
+            Ok(tag(name, &tokens[1..], options))
+        }
+        Some(&Identifier(ref name)) if options.blocks.contains_key(name) => {
+            let end_tag = format!("end{}", name);
+            let mut children = vec![];
+            let mut depth = 0;
+            loop {
+                let element = match iter.next() {
+                    Some(element) => element,
+                    None => {
+                        return Err(Error::parser(format!("Unexpected end of template, \
+                                                          expected {{% {} %}}",
+                                                         end_tag)))
+                    }
+                };
+                if let Tag(ref inner_tokens, _, _) = *element {
+                    if let Some(&Identifier(ref inner)) = inner_tokens.first() {
+                        if options.blocks.contains_key(inner) {
+                            depth += 1;
+                        } else if inner.starts_with("end") {
+                            if depth == 0 {
+                                if *inner == end_tag {
+                                    break;
+                                }
+                                return Err(Error::parser(format!("Expected {{% {} %}}, \
+                                                                  found {{% {} %}}",
+                                                                 end_tag, inner)));
+                            }
+                            depth -= 1;
+                        }
+                    }
+                }
+                children.push(element.clone());
+            }
+            let block = options.blocks.get(name).unwrap();
+            block(name, &tokens[1..], children, options)
+        }
+        x => Err(Error::parser(format!("Expected a tag identifier, found {:?}", x))),
+    }
+}
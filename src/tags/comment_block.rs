@@ -0,0 +1,15 @@
+use Renderable;
+use LiquidOptions;
+use lexer::Token;
+use lexer::Element;
+use text::Text;
+use error::Result;
+
+/// `{% comment %}...{% endcomment %}` renders to nothing.
+pub fn comment_block(_tag_name: &str,
+                     _arguments: &[Token],
+                     _tokens: Vec<Element>,
+                     _options: &LiquidOptions)
+                     -> Result<Box<Renderable>> {
+    Ok(Box::new(Text::new("")))
+}
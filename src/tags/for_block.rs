@@ -1,8 +1,11 @@
+use std::collections::HashMap;
 use Renderable;
 use context::Context;
+use ErrorMode;
 use LiquidOptions;
 use lexer::{Token, Element};
-use lexer::Token::Identifier;
+use lexer::Token::{Identifier, NumberLiteral, OpenRound, CloseRound, DotDot, Colon};
+use lexer::Element::Tag;
 use parser::parse;
 use template::Template;
 use value::Value;
@@ -13,31 +16,207 @@ use std::default::Default;
 #[cfg(test)]
 use lexer::tokenize;
 
+/// A single bound of a `(start..end)` range. Either a numeric literal baked in
+/// at parse time or an identifier resolved against the context at render time.
+enum RangeBound {
+    Literal(f32),
+    Identifier(String),
+}
+
+/// Where a `for` loop draws its values from. Both variants share the same loop
+/// body; only the way the `Vec<Value>` is produced differs. The array source is
+/// a dotted/bracketed path (e.g. `order.items`), not just a flat key.
+enum Source {
+    Array(String),
+    Range(RangeBound, RangeBound),
+}
+
 struct For {
     var_name: String,
-    array_id: String,
-    inner: Template,
+    source: Source,
+    item_template: Template,
+    else_template: Option<Template>,
+    limit: Option<usize>,
+    offset: usize,
+    reversed: bool,
+}
+
+fn get_array(context: &mut Context, path: &str) -> Result<Vec<Value>> {
+    let array = match context.get_path(path) {
+        Some(&Value::Array(ref x)) => Some(x.clone()),
+        _ => None,
+    };
+    match array {
+        Some(x) => Ok(x),
+        // Under `Lax`/`Warn` iterating a non-array is not fatal: it simply
+        // yields nothing (and is recorded as a warning under `Warn`).
+        None => {
+            let error = Error::render(format!("Tried to iterate over {}, which is not supported.",
+                                              path));
+            match context.error_mode() {
+                ErrorMode::Strict => Err(error),
+                ErrorMode::Warn => {
+                    context.warn(error);
+                    Ok(vec![])
+                }
+                ErrorMode::Lax => Ok(vec![]),
+            }
+        }
+    }
+}
+
+fn resolve_bound(context: &mut Context, bound: &RangeBound) -> Result<i32> {
+    match *bound {
+        RangeBound::Literal(x) => Ok(x as i32),
+        RangeBound::Identifier(ref id) => {
+            match context.get_path(id) {
+                Some(&Value::Num(x)) => Ok(x as i32),
+                _ => Err(Error::render(format!("Range bound '{}' is not a number.", id))),
+            }
+        }
+    }
 }
 
-fn get_array(context: &mut Context, array_id: &str) -> Result<Vec<Value>> {
-    match context.get_val(array_id) {
-        Some(&Value::Array(ref x)) => Ok(x.clone()),
-        x => Err(Error::Render(format!("Tried to iterate over {:?}, which is not supported.", x))),
+/// Resolves a `Source` into the list of values to iterate over. A range whose
+/// start is greater than its end yields an empty sequence rather than an error.
+fn resolve_source(context: &mut Context, source: &Source) -> Result<Vec<Value>> {
+    match *source {
+        Source::Array(ref id) => get_array(context, id),
+        Source::Range(ref start, ref end) => {
+            let start = try!(resolve_bound(context, start));
+            let end = try!(resolve_bound(context, end));
+            let mut ret = vec![];
+            for n in start..(end + 1) {
+                ret.push(Value::Num(n as f32));
+            }
+            Ok(ret)
+        }
     }
 }
 
 impl Renderable for For {
     fn render(&self, context: &mut Context) -> Result<Option<String>> {
-        let arr = try!(get_array(context, &self.array_id));
+        let mut values = try!(resolve_source(context, &self.source));
+
+        // Apply `offset`, `limit` and `reversed` in the order Liquid does.
+        if self.offset >= values.len() {
+            values.clear();
+        } else {
+            values = values.split_off(self.offset);
+        }
+        if let Some(limit) = self.limit {
+            values.truncate(limit);
+        }
+        if self.reversed {
+            values.reverse();
+        }
+
+        if values.is_empty() {
+            return match self.else_template {
+                Some(ref template) => template.render(context),
+                None => Ok(Some("".to_owned())),
+            };
+        }
+
+        // Remember anything we are about to shadow so nested loops are sound.
+        let saved_forloop = context.get_val("forloop").cloned();
+        let saved_var = context.get_val(&self.var_name).cloned();
+
+        let length = values.len();
         let mut ret = String::new();
-        for i in arr {
-            context.set_val(&self.var_name, i);
-            ret = ret + &try!(self.inner.render(context)).unwrap_or("".to_owned());
+        for (i, v) in values.into_iter().enumerate() {
+            let mut forloop = HashMap::new();
+            forloop.insert("index".to_owned(), Value::Num((i + 1) as f32));
+            forloop.insert("index0".to_owned(), Value::Num(i as f32));
+            forloop.insert("rindex".to_owned(), Value::Num((length - i) as f32));
+            forloop.insert("rindex0".to_owned(), Value::Num((length - i - 1) as f32));
+            forloop.insert("first".to_owned(), Value::Bool(i == 0));
+            forloop.insert("last".to_owned(), Value::Bool(i == length - 1));
+            forloop.insert("length".to_owned(), Value::Num(length as f32));
+            context.set_val("forloop", Value::Object(forloop));
+
+            context.set_val(&self.var_name, v);
+            ret = ret + &try!(self.item_template.render(context)).unwrap_or("".to_owned());
+        }
+
+        // Restore the shadowed bindings for the enclosing scope.
+        if let Some(forloop) = saved_forloop {
+            context.set_val("forloop", forloop);
+        }
+        if let Some(var) = saved_var {
+            context.set_val(&self.var_name, var);
         }
+
         Ok(Some(ret))
     }
 }
 
+/// Parses a single range bound: either a numeric literal or an identifier.
+fn parse_bound(arg: Option<&Token>) -> Result<RangeBound> {
+    match arg {
+        Some(&NumberLiteral(x)) => Ok(RangeBound::Literal(x)),
+        Some(&Identifier(ref x)) => Ok(RangeBound::Identifier(x.clone())),
+        x => Err(Error::parser(format!("Expected a range bound, found {:?}", x))),
+    }
+}
+
+/// Reads the numeric argument of a `limit:`/`offset:` modifier, consuming the
+/// expected `:` separator first.
+fn parse_modifier_value<'a, T>(args: &mut T) -> Result<usize>
+    where T: Iterator<Item = &'a Token>
+{
+    match args.next() {
+        Some(&Colon) => (),
+        x => return Err(Error::parser(format!("Expected ':', found {:?}", x))),
+    }
+    match args.next() {
+        Some(&NumberLiteral(x)) => Ok(x as usize),
+        x => Err(Error::parser(format!("Expected a number, found {:?}", x))),
+    }
+}
+
+/// Splits the block body on this block's own `{% else %}`, returning the tokens
+/// before it and, if present, those after it.
+///
+/// The body is a flat `Vec<Element>` that still contains any nested blocks'
+/// markers, so we track nesting depth (mirroring `parser::parse_tag`) and split
+/// only on a depth-0 `else` — a nested `{% for %}`/`{% if %}`'s `else` belongs
+/// to that inner block, not this one.
+fn split_else(tokens: Vec<Element>,
+              options: &LiquidOptions)
+              -> (Vec<Element>, Option<Vec<Element>>) {
+    let split_at = {
+        let mut depth = 0;
+        let mut found = None;
+        for (i, token) in tokens.iter().enumerate() {
+            if let Tag(ref tag_tokens, _, _) = *token {
+                if let Some(&Identifier(ref name)) = tag_tokens.first() {
+                    if options.blocks.contains_key(name) {
+                        depth += 1;
+                    } else if name.starts_with("end") {
+                        if depth > 0 {
+                            depth -= 1;
+                        }
+                    } else if name == "else" && depth == 0 {
+                        found = Some(i);
+                        break;
+                    }
+                }
+            }
+        }
+        found
+    };
+    match split_at {
+        Some(i) => {
+            let mut before = tokens;
+            let after = before.split_off(i + 1);
+            before.truncate(i);
+            (before, Some(after))
+        }
+        None => (tokens, None),
+    }
+}
+
 pub fn for_block(_tag_name: &str,
                  arguments: &[Token],
                  tokens: Vec<Element>,
@@ -45,28 +224,62 @@ pub fn for_block(_tag_name: &str,
                  -> Result<Box<Renderable>> {
     let mut args = arguments.iter();
 
-    let inner = try!(parse(&tokens, options));
-
     let var_name = match args.next() {
         Some(&Identifier(ref x)) => x.clone(),
-        x => return Err(Error::Parser(format!("Expected an identifier, found {:?}", x))),
+        x => return Err(Error::parser(format!("Expected an identifier, found {:?}", x))),
     };
 
     match args.next() {
         Some(&Identifier(ref x)) if x == "in" => (),
-        x => return Err(Error::Parser(format!("Expected 'in', found {:?}", x))),
+        x => return Err(Error::parser(format!("Expected 'in', found {:?}", x))),
     }
 
-    // TODO implement ranges
-    let array_id = match args.next() {
-        Some(&Identifier(ref x)) => x.clone(),
-        x => return Err(Error::Parser(format!("Expected an identifier, found {:?}", x))),
+    let source = match args.next() {
+        Some(&Identifier(ref x)) => Source::Array(x.clone()),
+        Some(&OpenRound) => {
+            let start = try!(parse_bound(args.next()));
+            match args.next() {
+                Some(&DotDot) => (),
+                x => return Err(Error::parser(format!("Expected '..', found {:?}", x))),
+            }
+            let end = try!(parse_bound(args.next()));
+            match args.next() {
+                Some(&CloseRound) => (),
+                x => return Err(Error::parser(format!("Expected ')', found {:?}", x))),
+            }
+            Source::Range(start, end)
+        }
+        x => return Err(Error::parser(format!("Expected an identifier or range, found {:?}", x))),
+    };
+
+    let mut limit = None;
+    let mut offset = 0;
+    let mut reversed = false;
+    loop {
+        match args.next() {
+            None => break,
+            Some(&Identifier(ref x)) if x == "reversed" => reversed = true,
+            Some(&Identifier(ref x)) if x == "limit" => limit = Some(try!(parse_modifier_value(&mut args))),
+            Some(&Identifier(ref x)) if x == "offset" => offset = try!(parse_modifier_value(&mut args)),
+            x => return Err(Error::parser(format!("Expected a for modifier, found {:?}", x))),
+        }
+    }
+
+    let (item_tokens, else_tokens) = split_else(tokens, options);
+    let item_template = Template::new(try!(parse(&item_tokens, options)));
+    let else_template = match else_tokens {
+        Some(tokens) => Some(Template::new(try!(parse(&tokens, options)))),
+        None => None,
     };
 
     Ok(Box::new(For {
         var_name: var_name,
-        array_id: array_id,
-        inner: Template::new(inner),
+        source: source,
+        item_template: item_template,
+        else_template: else_template,
+        limit: limit,
+        offset: offset,
+        reversed: reversed,
     }))
 }
 
@@ -89,3 +302,106 @@ fn test_for() {
     assert_eq!(for_tag.unwrap().render(&mut data).unwrap(),
                Some("test 22 test 23 test 24 test wat ".to_owned()));
 }
+
+#[test]
+fn test_for_range() {
+    let options: LiquidOptions = Default::default();
+    let for_tag = for_block("for",
+                            &[Identifier("i".to_owned()),
+                              Identifier("in".to_owned()),
+                              OpenRound,
+                              NumberLiteral(1f32),
+                              DotDot,
+                              Identifier("limit".to_owned()),
+                              CloseRound],
+                            tokenize("{{i}} ").unwrap(),
+                            &options);
+
+    let mut data: Context = Default::default();
+    data.set_val("limit", Value::Num(3f32));
+    assert_eq!(for_tag.unwrap().render(&mut data).unwrap(),
+               Some("1 2 3 ".to_owned()));
+}
+
+#[test]
+fn test_for_empty_range() {
+    let options: LiquidOptions = Default::default();
+    let for_tag = for_block("for",
+                            &[Identifier("i".to_owned()),
+                              Identifier("in".to_owned()),
+                              OpenRound,
+                              NumberLiteral(5f32),
+                              DotDot,
+                              NumberLiteral(1f32),
+                              CloseRound],
+                            tokenize("{{i}} ").unwrap(),
+                            &options);
+
+    let mut data: Context = Default::default();
+    assert_eq!(for_tag.unwrap().render(&mut data).unwrap(),
+               Some("".to_owned()));
+}
+
+#[test]
+fn test_for_limit_offset_reversed() {
+    let options: LiquidOptions = Default::default();
+    let for_tag = for_block("for",
+                            &[Identifier("name".to_owned()),
+                              Identifier("in".to_owned()),
+                              Identifier("array".to_owned()),
+                              Identifier("offset".to_owned()),
+                              Colon,
+                              NumberLiteral(1f32),
+                              Identifier("limit".to_owned()),
+                              Colon,
+                              NumberLiteral(2f32),
+                              Identifier("reversed".to_owned())],
+                            tokenize("{{name}} ").unwrap(),
+                            &options);
+
+    let mut data: Context = Default::default();
+    data.set_val("array",
+                 Value::Array(vec![Value::Num(1f32),
+                                   Value::Num(2f32),
+                                   Value::Num(3f32),
+                                   Value::Num(4f32)]));
+    assert_eq!(for_tag.unwrap().render(&mut data).unwrap(),
+               Some("3 2 ".to_owned()));
+}
+
+#[test]
+fn test_for_dotted_path() {
+    let options: LiquidOptions = Default::default();
+    let for_tag = for_block("for",
+                            &[Identifier("item".to_owned()),
+                              Identifier("in".to_owned()),
+                              Identifier("order.items".to_owned())],
+                            tokenize("{{item}} ").unwrap(),
+                            &options);
+
+    let mut order = HashMap::new();
+    order.insert("items".to_owned(),
+                 Value::Array(vec![Value::Num(1f32), Value::Num(2f32)]));
+    let mut data: Context = Default::default();
+    data.set_val("order", Value::Object(order));
+    assert_eq!(for_tag.unwrap().render(&mut data).unwrap(),
+               Some("1 2 ".to_owned()));
+}
+
+#[test]
+fn test_for_else() {
+    let options: LiquidOptions = Default::default();
+    let mut body = tokenize("{{name}} ").unwrap();
+    body.extend(tokenize("{% else %}empty").unwrap());
+    let for_tag = for_block("for",
+                            &[Identifier("name".to_owned()),
+                              Identifier("in".to_owned()),
+                              Identifier("array".to_owned())],
+                            body,
+                            &options);
+
+    let mut data: Context = Default::default();
+    data.set_val("array", Value::Array(vec![]));
+    assert_eq!(for_tag.unwrap().render(&mut data).unwrap(),
+               Some("empty".to_owned()));
+}
@@ -0,0 +1,155 @@
+use Renderable;
+use LiquidOptions;
+use context::Context;
+use value::Value;
+use lexer::Token;
+use lexer::Token::{Identifier, Comparison};
+use lexer::Element;
+use lexer::Element::Tag;
+use lexer::ComparisonOperator;
+use lexer::ComparisonOperator::*;
+use output::{Argument, parse_argument};
+use parser::parse;
+use template::Template;
+use error::{Error, Result};
+
+struct If {
+    left: Argument,
+    comparison: Option<(ComparisonOperator, Argument)>,
+    if_true: Template,
+    if_false: Option<Template>,
+}
+
+/// Liquid truthiness: only `false` is falsy (there is no `nil` value here).
+fn truthy(value: &Value) -> bool {
+    match *value {
+        Value::Bool(b) => b,
+        _ => true,
+    }
+}
+
+fn compare(operator: &ComparisonOperator, left: &Value, right: &Value) -> bool {
+    match *operator {
+        Equals => left == right,
+        NotEquals => left != right,
+        LessThan => num_compare(left, right, |a, b| a < b),
+        GreaterThan => num_compare(left, right, |a, b| a > b),
+        LessThanEquals => num_compare(left, right, |a, b| a <= b),
+        GreaterThanEquals => num_compare(left, right, |a, b| a >= b),
+        Contains => contains(left, right),
+    }
+}
+
+fn num_compare<F>(left: &Value, right: &Value, f: F) -> bool
+    where F: Fn(f32, f32) -> bool
+{
+    match (left, right) {
+        (&Value::Num(a), &Value::Num(b)) => f(a, b),
+        _ => false,
+    }
+}
+
+fn contains(haystack: &Value, needle: &Value) -> bool {
+    match *haystack {
+        Value::Array(ref items) => items.iter().any(|item| item == needle),
+        Value::Str(ref s) => {
+            match *needle {
+                Value::Str(ref n) => s.contains(n.as_str()),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Splits a block body on this block's own `{% else %}`, tracking nesting depth
+/// so a nested block's `else` is not mistaken for this one's (mirroring
+/// `parser::parse_tag`).
+fn split_else(tokens: Vec<Element>,
+              options: &LiquidOptions)
+              -> (Vec<Element>, Option<Vec<Element>>) {
+    let split_at = {
+        let mut depth = 0;
+        let mut found = None;
+        for (i, token) in tokens.iter().enumerate() {
+            if let Tag(ref tag_tokens, _, _) = *token {
+                if let Some(&Identifier(ref name)) = tag_tokens.first() {
+                    if options.blocks.contains_key(name) {
+                        depth += 1;
+                    } else if name.starts_with("end") {
+                        if depth > 0 {
+                            depth -= 1;
+                        }
+                    } else if name == "else" && depth == 0 {
+                        found = Some(i);
+                        break;
+                    }
+                }
+            }
+        }
+        found
+    };
+    match split_at {
+        Some(i) => {
+            let mut before = tokens;
+            let after = before.split_off(i + 1);
+            before.truncate(i);
+            (before, Some(after))
+        }
+        None => (tokens, None),
+    }
+}
+
+impl Renderable for If {
+    fn render(&self, context: &mut Context) -> Result<Option<String>> {
+        let left = try!(self.left.evaluate(context));
+        let result = match self.comparison {
+            Some((ref operator, ref right)) => {
+                let right = try!(right.evaluate(context));
+                compare(operator, &left, &right)
+            }
+            None => truthy(&left),
+        };
+
+        if result {
+            self.if_true.render(context)
+        } else {
+            match self.if_false {
+                Some(ref template) => template.render(context),
+                None => Ok(Some("".to_owned())),
+            }
+        }
+    }
+}
+
+pub fn if_block(_tag_name: &str,
+                arguments: &[Token],
+                tokens: Vec<Element>,
+                options: &LiquidOptions)
+                -> Result<Box<Renderable>> {
+    let mut args = arguments.iter();
+
+    let left = try!(parse_argument(args.next()));
+    let comparison = match args.next() {
+        Some(&Comparison(ref operator)) => {
+            let right = try!(parse_argument(args.next()));
+            Some((operator.clone(), right))
+        }
+        None => None,
+        x => return Err(Error::parser(format!("Expected a comparison operator, found {:?}", x))),
+    };
+
+    let (true_tokens, false_tokens) = split_else(tokens, options);
+    let if_true = Template::new(try!(parse(&true_tokens, options)));
+    let if_false = match false_tokens {
+        Some(tokens) => Some(Template::new(try!(parse(&tokens, options)))),
+        None => None,
+    };
+
+    Ok(Box::new(If {
+        left: left,
+        comparison: comparison,
+        if_true: if_true,
+        if_false: if_false,
+    }))
+}
@@ -0,0 +1,9 @@
+pub use self::for_block::for_block;
+pub use self::if_block::if_block;
+pub use self::raw_block::raw_block;
+pub use self::comment_block::comment_block;
+
+mod for_block;
+mod if_block;
+mod raw_block;
+mod comment_block;
@@ -0,0 +1,26 @@
+use Renderable;
+use LiquidOptions;
+use lexer::Token;
+use lexer::Element;
+use lexer::Element::*;
+use text::Text;
+use error::Result;
+
+/// `{% raw %}...{% endraw %}` emits its body verbatim, without interpreting any
+/// markup inside it. The original source of each inner element is concatenated
+/// back together.
+pub fn raw_block(_tag_name: &str,
+                 _arguments: &[Token],
+                 tokens: Vec<Element>,
+                 _options: &LiquidOptions)
+                 -> Result<Box<Renderable>> {
+    let content = tokens.iter()
+        .map(|element| match *element {
+            Expression(_, ref markup, _) |
+            Tag(_, ref markup, _) => markup.clone(),
+            Raw(ref text, _) => text.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join("");
+    Ok(Box::new(Text::new(&content)))
+}
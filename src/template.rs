@@ -0,0 +1,41 @@
+use Renderable;
+use ErrorMode;
+use context::Context;
+use error::Result;
+
+/// A parsed template: an ordered list of [Renderables](trait.Renderable.html)
+/// that are rendered in sequence and concatenated.
+pub struct Template {
+    pub elements: Vec<Box<Renderable>>,
+    error_mode: Option<ErrorMode>,
+}
+
+impl Template {
+    pub fn new(elements: Vec<Box<Renderable>>) -> Template {
+        Template {
+            elements: elements,
+            error_mode: None,
+        }
+    }
+
+    /// Seeds the render context with this [ErrorMode](../enum.ErrorMode.html)
+    /// before rendering. `liquid::parse` sets it from `LiquidOptions`; the inner
+    /// templates blocks build leave it unset so they inherit the active mode.
+    pub fn with_error_mode(mut self, error_mode: ErrorMode) -> Template {
+        self.error_mode = Some(error_mode);
+        self
+    }
+
+    pub fn render(&self, context: &mut Context) -> Result<Option<String>> {
+        if let Some(error_mode) = self.error_mode {
+            context.set_error_mode(error_mode);
+        }
+        let mut buffer = String::new();
+        for element in &self.elements {
+            if let Some(text) = try!(element.render(context)) {
+                buffer = buffer + &text;
+            }
+        }
+        Ok(Some(buffer))
+    }
+}
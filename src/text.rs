@@ -0,0 +1,20 @@
+use Renderable;
+use context::Context;
+use error::Result;
+
+/// A chunk of literal template text that renders to itself.
+pub struct Text {
+    text: String,
+}
+
+impl Text {
+    pub fn new(text: &str) -> Text {
+        Text { text: text.to_owned() }
+    }
+}
+
+impl Renderable for Text {
+    fn render(&self, _context: &mut Context) -> Result<Option<String>> {
+        Ok(Some(self.text.clone()))
+    }
+}
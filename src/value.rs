@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use self::Value::*;
+
+/// A value living in a template [Context](../struct.Context.html).
+///
+/// `Object` and `Bool` were added alongside the `forloop` drop and structured
+/// variable lookups; the original set was only `Num`/`Str`/`Array`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Num(f32),
+    Str(String),
+    Bool(bool),
+    Array(Vec<Value>),
+    Object(HashMap<String, Value>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Num(ref x) => write!(f, "{}", x),
+            Str(ref x) => write!(f, "{}", x),
+            Bool(ref x) => write!(f, "{}", x),
+            Array(ref x) => {
+                let items = x.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+                write!(f, "{}", items.join(", "))
+            }
+            Object(ref x) => {
+                let items = x.iter()
+                    .map(|(k, v)| format!("{}: {}", k, v))
+                    .collect::<Vec<_>>();
+                write!(f, "{}", items.join(", "))
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use context::Context;
+use value::Value;
+
+/// A reference to a value in the [Context](../struct.Context.html) by a
+/// dotted/bracketed path such as `order.items[0].title`.
+pub struct Variable {
+    name: String,
+}
+
+impl Variable {
+    pub fn new(name: &str) -> Variable {
+        Variable { name: name.to_owned() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Resolves the path against `context`, returning an owned clone of the
+    /// referenced value or `None` if any segment is missing.
+    pub fn resolve(&self, context: &Context) -> Option<Value> {
+        context.get_path(&self.name).cloned()
+    }
+}